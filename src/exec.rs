@@ -0,0 +1,39 @@
+use crate::mfa;
+use crate::storage::Storage;
+use clap::Parser;
+
+#[derive(Debug, Parser)]
+pub(super) struct Opts {
+    #[clap(long)]
+    iam: String,
+    #[clap(long, default_value = "12h")]
+    duration: humantime::Duration,
+    #[clap(last = true)]
+    command: Vec<String>,
+}
+
+pub(super) async fn main(opts: Opts) -> anyhow::Result<()> {
+    let storage = Storage::open().await?;
+    let credentials = mfa::resolve(&storage, &opts.iam, opts.duration).await?;
+
+    let (program, args) = opts
+        .command
+        .split_first()
+        .ok_or_else(|| anyhow::format_err!("missing command"))?;
+    let mut command = tokio::process::Command::new(program);
+    command
+        .args(args)
+        .env("AWS_ACCESS_KEY_ID", &credentials.access_key_id)
+        .env("AWS_SECRET_ACCESS_KEY", &credentials.secret_access_key);
+    match &credentials.session_token {
+        Some(session_token) => command.env("AWS_SESSION_TOKEN", session_token),
+        None => command.env_remove("AWS_SESSION_TOKEN"),
+    };
+    match credentials.expiration {
+        Some(expiration) => command.env("AWS_CREDENTIALS_EXPIRATION", expiration.to_rfc3339()),
+        None => command.env_remove("AWS_CREDENTIALS_EXPIRATION"),
+    };
+    let status = command.status().await?;
+
+    std::process::exit(status.code().unwrap_or(1));
+}
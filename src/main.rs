@@ -1,5 +1,8 @@
+mod exec;
 mod mfa;
 mod rotate;
+mod serve;
+mod show;
 mod storage;
 
 use clap::{Parser, Subcommand};
@@ -14,8 +17,11 @@ struct Opts {
 
 #[derive(Debug, Subcommand)]
 enum Command {
+    Exec(exec::Opts),
     Mfa(mfa::Opts),
     Rotate(rotate::Opts),
+    Serve(serve::Opts),
+    Show(show::Opts),
 }
 
 #[tokio::main]
@@ -28,7 +34,10 @@ async fn main() -> anyhow::Result<()> {
     let opts = Opts::parse();
     tracing::debug!(opts = ?opts);
     match opts.command {
+        Command::Exec(opts) => exec::main(opts).await,
         Command::Mfa(opts) => mfa::main(opts).await,
         Command::Rotate(opts) => rotate::main(opts).await,
+        Command::Serve(opts) => serve::main(opts).await,
+        Command::Show(opts) => show::main(opts).await,
     }
 }
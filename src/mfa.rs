@@ -1,4 +1,4 @@
-use crate::storage::{Credentials, MfaDevice, Storage};
+use crate::storage::{Credentials, MfaDevice, Storage, StorageBackend};
 use chrono::offset::Utc;
 use chrono::DateTime;
 use clap::Parser;
@@ -15,12 +15,47 @@ pub(super) struct Opts {
 }
 
 pub(super) async fn main(opts: Opts) -> anyhow::Result<()> {
-    let mut storage = Storage::load().await?;
+    let storage = Storage::open().await?;
+    let credentials = resolve(&storage, &opts.iam, opts.duration).await?;
 
+    // https://docs.aws.amazon.com/cli/latest/userguide/cli-configure-sourcing-external.html
+    #[derive(Serialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Output<'a> {
+        version: u32,
+        access_key_id: &'a str,
+        secret_access_key: &'a str,
+        session_token: Option<&'a str>,
+        expiration: Option<DateTime<Utc>>,
+    }
+    serde_json::to_writer_pretty(
+        io::stdout(),
+        &Output {
+            version: 1,
+            access_key_id: &credentials.access_key_id,
+            secret_access_key: &credentials.secret_access_key,
+            session_token: credentials.session_token.as_deref(),
+            expiration: credentials.expiration,
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Resolve the cached credentials for `iam`, refreshing the MFA-backed
+/// session token if it's missing or within a fifth of `duration` of
+/// expiring. Shared by the `mfa`, `exec` and `show` commands so they all
+/// reuse one cached session token and touch the MFA device only when it
+/// needs a refresh, not on every run.
+pub(crate) async fn resolve(
+    storage: &Storage<Box<dyn StorageBackend>>,
+    iam: &str,
+    duration: humantime::Duration,
+) -> anyhow::Result<Credentials> {
     let credentials = storage
-        .credentials
-        .get(&opts.iam)
-        .ok_or_else(|| anyhow::format_err!("missing credentials for {}", opts.iam))?;
+        .get_credentials(iam)
+        .await?
+        .ok_or_else(|| anyhow::format_err!("missing credentials for {}", iam))?;
     tracing::debug!(credentials = ?credentials);
     let config = aws_config::from_env()
         .credentials_provider(aws_credential_types::Credentials::from(credentials.clone()))
@@ -41,18 +76,17 @@ pub(super) async fn main(opts: Opts) -> anyhow::Result<()> {
         .ok_or_else(|| anyhow::format_err!("missing serial number"))?;
     tracing::debug!(serial_number = serial_number);
 
-    let credentials = storage.credentials.get(serial_number).cloned();
+    let credentials = storage.get_credentials(serial_number).await?;
     tracing::debug!(credentials = ?credentials);
 
-    let credentials = if let Some(credentials) = credentials.filter(|credentials| {
-        credentials.expiration
-            > Some(Utc::now() + chrono::Duration::seconds((opts.duration.as_secs() / 5) as _))
-    }) {
+    let credentials = if let Some(credentials) =
+        credentials.filter(|credentials| is_fresh(credentials, duration))
+    {
         credentials
     } else {
-        let token_code = match storage.mfa_devices.get(serial_number) {
+        let token_code = match storage.get_mfa_device(serial_number).await? {
             Some(MfaDevice::Ykoath(device)) => {
-                let name = device.name.clone();
+                let name = device.name;
                 tracing::debug!(ykoath.name = name);
                 tokio::task::spawn_blocking(move || ykoath(&name))
                     .await
@@ -66,7 +100,7 @@ pub(super) async fn main(opts: Opts) -> anyhow::Result<()> {
             .get_session_token()
             .serial_number(serial_number)
             .token_code(token_code)
-            .duration_seconds(opts.duration.as_secs() as _)
+            .duration_seconds(duration.as_secs() as _)
             .send()
             .await?;
         let credentials = Credentials::try_from(
@@ -77,35 +111,22 @@ pub(super) async fn main(opts: Opts) -> anyhow::Result<()> {
         tracing::debug!(credentials = ?credentials);
 
         storage
-            .credentials
-            .insert(serial_number.to_owned(), credentials.clone());
-        storage.save().await?;
+            .put_credentials(serial_number, credentials.clone())
+            .await?;
 
         credentials
     };
 
-    // https://docs.aws.amazon.com/cli/latest/userguide/cli-configure-sourcing-external.html
-    #[derive(Serialize)]
-    #[serde(rename_all = "PascalCase")]
-    struct Output<'a> {
-        version: u32,
-        access_key_id: &'a str,
-        secret_access_key: &'a str,
-        session_token: Option<&'a str>,
-        expiration: Option<DateTime<Utc>>,
-    }
-    serde_json::to_writer_pretty(
-        io::stdout(),
-        &Output {
-            version: 1,
-            access_key_id: &credentials.access_key_id,
-            secret_access_key: &credentials.secret_access_key,
-            session_token: credentials.session_token.as_deref(),
-            expiration: credentials.expiration,
-        },
-    )?;
+    Ok(credentials)
+}
 
-    Ok(())
+/// Whether `credentials` are still valid for at least a fifth of `duration`,
+/// i.e. don't need an MFA-backed refresh yet. Shared with `serve`, which
+/// keeps its own short-lived cache to avoid re-entering `resolve`'s network
+/// path (a `list_mfa_devices` call at minimum) on every poll.
+pub(crate) fn is_fresh(credentials: &Credentials, duration: humantime::Duration) -> bool {
+    credentials.expiration
+        > Some(Utc::now() + chrono::Duration::seconds((duration.as_secs() / 5) as _))
 }
 
 fn ykoath(name: &str) -> anyhow::Result<String> {
@@ -1,20 +1,37 @@
 use crate::storage::Storage;
+use chrono::offset::Utc;
 use clap::Parser;
 
 #[derive(Debug, Parser)]
 pub(super) struct Opts {
     #[clap(long)]
     iam: String,
+    /// Only rotate if the stored access key is older than this; otherwise
+    /// exit as a no-op. Omit to always rotate, e.g. for a manual run. A stored
+    /// key with no recorded creation time is always rotated.
+    #[clap(long)]
+    max_age: Option<humantime::Duration>,
 }
 
 pub(super) async fn main(opts: Opts) -> anyhow::Result<()> {
-    let mut storage = Storage::load().await?;
+    let storage = Storage::open().await?;
 
     let credentials = storage
-        .credentials
-        .get(&opts.iam)
+        .get_credentials(&opts.iam)
+        .await?
         .ok_or_else(|| anyhow::format_err!("missing credentials for {}", opts.iam))?;
     tracing::debug!(credentials = ?credentials);
+
+    if let Some(max_age) = opts.max_age {
+        if let Some(created_at) = credentials.created_at {
+            let age = Utc::now() - created_at;
+            if age < chrono::Duration::from_std(*max_age)? {
+                tracing::info!(?age, ?max_age, "access key is not old enough, skipping rotation");
+                return Ok(());
+            }
+        }
+    }
+
     let config = aws_config::from_env()
         .credentials_provider(aws_types::Credentials::from(credentials.clone()))
         .load()
@@ -44,9 +61,7 @@ pub(super) async fn main(opts: Opts) -> anyhow::Result<()> {
         .ok_or_else(|| anyhow::format_err!("missing access_key"))?
         .try_into()?;
     tracing::debug!(credentials = ?credentials);
-    storage.credentials.insert(opts.iam.clone(), credentials);
-
-    storage.save().await?;
+    storage.put_credentials(&opts.iam, credentials).await?;
 
     Ok(())
 }
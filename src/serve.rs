@@ -0,0 +1,141 @@
+use crate::mfa;
+use crate::storage::{Credentials, Storage, StorageBackend};
+use chrono::offset::Utc;
+use chrono::DateTime;
+use clap::Parser;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use serde::Serialize;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Parser)]
+pub(super) struct Opts {
+    #[clap(long)]
+    iam: String,
+    #[clap(long, default_value = "12h")]
+    duration: humantime::Duration,
+    #[clap(long, default_value = "127.0.0.1:0")]
+    bind: SocketAddr,
+}
+
+/// Everything a request handler needs, shared across connections so every
+/// client hits the same cached credentials and shares one MFA touch per
+/// refresh.
+struct State {
+    storage: Storage<Box<dyn StorageBackend>>,
+    iam: String,
+    duration: humantime::Duration,
+    token: String,
+    /// The last resolved credentials, reused while still fresh so that
+    /// concurrent requests (and repeated polling) share one MFA touch
+    /// instead of each re-entering `mfa::resolve`'s network path.
+    credentials: Mutex<Option<Credentials>>,
+}
+
+pub(super) async fn main(opts: Opts) -> anyhow::Result<()> {
+    let storage = Storage::open().await?;
+    let token = std::env::var("AWS_CREDENTIALS_HELPER_SERVE_TOKEN")
+        .unwrap_or_else(|_| uuid::Uuid::new_v4().to_string());
+
+    let state = Arc::new(State {
+        storage,
+        iam: opts.iam,
+        duration: opts.duration,
+        token: token.clone(),
+        credentials: Mutex::new(None),
+    });
+
+    let make_service = make_service_fn(move |_conn| {
+        let state = Arc::clone(&state);
+        async move { Ok::<_, Infallible>(service_fn(move |req| handle(Arc::clone(&state), req))) }
+    });
+
+    let server = Server::bind(&opts.bind).serve(make_service);
+    // https://docs.aws.amazon.com/sdkref/latest/guide/feature-container-credentials.html
+    eprintln!(
+        "AWS_CONTAINER_CREDENTIALS_FULL_URI=http://{}",
+        server.local_addr()
+    );
+    eprintln!("AWS_CONTAINER_CREDENTIALS_TOKEN={}", token);
+    server.await?;
+
+    Ok(())
+}
+
+async fn handle(state: Arc<State>, req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    if req.method() != Method::GET {
+        return Ok(respond(StatusCode::METHOD_NOT_ALLOWED, "method not allowed"));
+    }
+
+    // SDKs send the raw token as `Authorization`, with no `Bearer` scheme.
+    let authorized = req
+        .headers()
+        .get(hyper::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| bool::from(value.as_bytes().ct_eq(state.token.as_bytes())))
+        .unwrap_or(false);
+    if !authorized {
+        return Ok(respond(StatusCode::UNAUTHORIZED, "unauthorized"));
+    }
+
+    match resolve(&state).await {
+        Ok(credentials) => {
+            #[derive(Serialize)]
+            #[serde(rename_all = "PascalCase")]
+            struct Output<'a> {
+                access_key_id: &'a str,
+                secret_access_key: &'a str,
+                token: Option<&'a str>,
+                expiration: Option<DateTime<Utc>>,
+            }
+            let body = serde_json::to_vec(&Output {
+                access_key_id: &credentials.access_key_id,
+                secret_access_key: &credentials.secret_access_key,
+                token: credentials.session_token.as_deref(),
+                expiration: credentials.expiration,
+            })
+            .expect("serializing credentials does not fail");
+            Ok(Response::builder()
+                .status(StatusCode::OK)
+                .header(hyper::header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body))
+                .expect("building a response from a fixed set of headers does not fail"))
+        }
+        Err(err) => {
+            tracing::error!(error = ?err, "failed to resolve credentials");
+            Ok(respond(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "failed to resolve credentials",
+            ))
+        }
+    }
+}
+
+/// Reuse the cached credentials while they're still fresh; only fall
+/// through to `mfa::resolve` (and whatever MFA touch that entails) once
+/// they've gone stale, so a handful of pollers share one refresh instead of
+/// each re-resolving on every request.
+async fn resolve(state: &State) -> anyhow::Result<Credentials> {
+    let mut cached = state.credentials.lock().await;
+    if let Some(credentials) = cached
+        .as_ref()
+        .filter(|credentials| mfa::is_fresh(credentials, state.duration))
+    {
+        return Ok(credentials.clone());
+    }
+
+    let credentials = mfa::resolve(&state.storage, &state.iam, state.duration).await?;
+    *cached = Some(credentials.clone());
+    Ok(credentials)
+}
+
+fn respond(status: StatusCode, message: &str) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .body(Body::from(message.to_owned()))
+        .expect("building a response from a fixed set of headers does not fail")
+}
@@ -0,0 +1,42 @@
+use crate::mfa;
+use crate::storage::Storage;
+use clap::Parser;
+
+#[derive(Debug, Parser)]
+pub(super) struct Opts {
+    #[clap(long)]
+    iam: String,
+    #[clap(long, default_value = "12h")]
+    duration: humantime::Duration,
+}
+
+pub(super) async fn main(opts: Opts) -> anyhow::Result<()> {
+    let storage = Storage::open().await?;
+    let credentials = mfa::resolve(&storage, &opts.iam, opts.duration).await?;
+
+    println!(
+        "export AWS_ACCESS_KEY_ID={}",
+        shell_quote(&credentials.access_key_id)
+    );
+    println!(
+        "export AWS_SECRET_ACCESS_KEY={}",
+        shell_quote(&credentials.secret_access_key)
+    );
+    match &credentials.session_token {
+        Some(session_token) => println!("export AWS_SESSION_TOKEN={}", shell_quote(session_token)),
+        None => println!("unset AWS_SESSION_TOKEN"),
+    }
+    match credentials.expiration {
+        Some(expiration) => println!(
+            "export AWS_CREDENTIALS_EXPIRATION={}",
+            shell_quote(&expiration.to_rfc3339())
+        ),
+        None => println!("unset AWS_CREDENTIALS_EXPIRATION"),
+    }
+
+    Ok(())
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
@@ -0,0 +1,58 @@
+use super::{Credentials, MfaDevice, StorageBackend};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tokio::fs;
+use tokio::sync::Mutex;
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct Data {
+    #[serde(default)]
+    credentials: BTreeMap<String, Credentials>,
+    #[serde(default)]
+    mfa_devices: BTreeMap<String, MfaDevice>,
+}
+
+/// The original backend: a single plaintext `~/.aws/credentials-helper.json`.
+pub(crate) struct JsonFile {
+    data: Mutex<Data>,
+}
+
+impl JsonFile {
+    pub(crate) async fn load() -> anyhow::Result<Self> {
+        let data = serde_json::from_slice(&fs::read(Self::path()?).await?)?;
+        Ok(Self {
+            data: Mutex::new(data),
+        })
+    }
+
+    async fn save(&self, data: &Data) -> anyhow::Result<()> {
+        Ok(fs::write(Self::path()?, serde_json::to_vec_pretty(data)?).await?)
+    }
+
+    fn path() -> anyhow::Result<PathBuf> {
+        Ok(dirs::home_dir()
+            .ok_or_else(|| anyhow::format_err!("missing home directory"))?
+            .join(".aws")
+            .join("credentials-helper.json"))
+    }
+}
+
+#[async_trait]
+impl StorageBackend for JsonFile {
+    async fn get_credentials(&self, key: &str) -> anyhow::Result<Option<Credentials>> {
+        Ok(self.data.lock().await.credentials.get(key).cloned())
+    }
+
+    async fn put_credentials(&self, key: &str, credentials: Credentials) -> anyhow::Result<()> {
+        let mut data = self.data.lock().await;
+        data.credentials.insert(key.to_owned(), credentials);
+        self.save(&data).await
+    }
+
+    async fn get_mfa_device(&self, key: &str) -> anyhow::Result<Option<MfaDevice>> {
+        Ok(self.data.lock().await.mfa_devices.get(key).cloned())
+    }
+}
@@ -0,0 +1,236 @@
+use super::{Credentials, MfaDevice, StorageBackend};
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::offset::Utc;
+use chrono::DateTime;
+use crypto_secretbox::aead::rand_core::{OsRng, RngCore};
+use crypto_secretbox::aead::{Aead, AeadCore};
+use crypto_secretbox::{KeyInit, Nonce, XSalsa20Poly1305};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::PathBuf;
+use tokio::fs;
+use tokio::sync::Mutex;
+
+/// Sealed with the derived key on first use; a wrong passphrase fails to
+/// open this rather than silently producing garbage credentials.
+const VERIFY_PLAINTEXT: &[u8] = b"aws-credentials-helper/verify";
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct File {
+    #[serde(with = "base64_bytes")]
+    salt: Vec<u8>,
+    verify_blob: SealedBlob,
+    #[serde(default)]
+    credentials: BTreeMap<String, SealedCredentials>,
+    #[serde(default)]
+    mfa_devices: BTreeMap<String, MfaDevice>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct SealedBlob {
+    #[serde(with = "base64_bytes")]
+    nonce: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    ciphertext: Vec<u8>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct SealedCredentials {
+    access_key_id: String,
+    secret_access_key: SealedBlob,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    session_token: Option<SealedBlob>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expiration: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_at: Option<DateTime<Utc>>,
+}
+
+mod base64_bytes {
+    use super::BASE64;
+    use base64::Engine;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        bytes: &[u8],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&BASE64.encode(bytes))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        BASE64
+            .decode(String::deserialize(deserializer)?)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+struct Data {
+    salt: Vec<u8>,
+    verify_blob: SealedBlob,
+    credentials: BTreeMap<String, SealedCredentials>,
+    mfa_devices: BTreeMap<String, MfaDevice>,
+}
+
+/// An opt-in encrypted variant of [`super::JsonFile`]: every secret field is
+/// sealed with a key derived from a user passphrase, so the file on disk
+/// never holds a plaintext `secret_access_key` or `session_token`.
+pub(crate) struct EncryptedJsonFile {
+    key: [u8; 32],
+    data: Mutex<Data>,
+}
+
+impl EncryptedJsonFile {
+    pub(crate) async fn load() -> anyhow::Result<Self> {
+        let passphrase = Self::passphrase()?;
+        let (salt, verify_blob, credentials, mfa_devices) = match fs::read(Self::path()?).await {
+            Ok(bytes) => {
+                let file: File = serde_json::from_slice(&bytes)?;
+                (file.salt, Some(file.verify_blob), file.credentials, file.mfa_devices)
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                let mut salt = vec![0u8; 16];
+                OsRng.fill_bytes(&mut salt);
+                (salt, None, BTreeMap::new(), BTreeMap::new())
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let key = derive_key(&passphrase, &salt)?;
+        let verify_blob = match verify_blob {
+            Some(verify_blob) => {
+                if open(&key, &verify_blob)? != VERIFY_PLAINTEXT {
+                    anyhow::bail!("wrong passphrase");
+                }
+                verify_blob
+            }
+            None => seal(&key, VERIFY_PLAINTEXT),
+        };
+
+        Ok(Self {
+            key,
+            data: Mutex::new(Data {
+                salt,
+                verify_blob,
+                credentials,
+                mfa_devices,
+            }),
+        })
+    }
+
+    async fn save(&self, data: &Data) -> anyhow::Result<()> {
+        let file = File {
+            salt: data.salt.clone(),
+            verify_blob: data.verify_blob.clone(),
+            credentials: data.credentials.clone(),
+            mfa_devices: data.mfa_devices.clone(),
+        };
+        Ok(fs::write(Self::path()?, serde_json::to_vec_pretty(&file)?).await?)
+    }
+
+    fn path() -> anyhow::Result<PathBuf> {
+        Ok(dirs::home_dir()
+            .ok_or_else(|| anyhow::format_err!("missing home directory"))?
+            .join(".aws")
+            .join("credentials-helper.encrypted.json"))
+    }
+
+    fn passphrase() -> anyhow::Result<String> {
+        match std::env::var("AWS_CREDENTIALS_HELPER_PASSPHRASE") {
+            Ok(passphrase) => Ok(passphrase),
+            Err(std::env::VarError::NotPresent) => {
+                Ok(rpassword::prompt_password("Passphrase: ")?)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn seal_credentials(&self, credentials: &Credentials) -> SealedCredentials {
+        SealedCredentials {
+            access_key_id: credentials.access_key_id.clone(),
+            secret_access_key: seal(&self.key, credentials.secret_access_key.as_bytes()),
+            session_token: credentials
+                .session_token
+                .as_deref()
+                .map(|session_token| seal(&self.key, session_token.as_bytes())),
+            expiration: credentials.expiration,
+            created_at: credentials.created_at,
+        }
+    }
+
+    fn unseal_credentials(&self, sealed: &SealedCredentials) -> anyhow::Result<Credentials> {
+        Ok(Credentials {
+            access_key_id: sealed.access_key_id.clone(),
+            secret_access_key: String::from_utf8(open(&self.key, &sealed.secret_access_key)?)?,
+            session_token: sealed
+                .session_token
+                .as_ref()
+                .map(|session_token| -> anyhow::Result<_> {
+                    Ok(String::from_utf8(open(&self.key, session_token)?)?)
+                })
+                .transpose()?,
+            expiration: sealed.expiration,
+            created_at: sealed.created_at,
+        })
+    }
+}
+
+#[async_trait]
+impl StorageBackend for EncryptedJsonFile {
+    async fn get_credentials(&self, key: &str) -> anyhow::Result<Option<Credentials>> {
+        self.data
+            .lock()
+            .await
+            .credentials
+            .get(key)
+            .map(|sealed| self.unseal_credentials(sealed))
+            .transpose()
+    }
+
+    async fn put_credentials(&self, key: &str, credentials: Credentials) -> anyhow::Result<()> {
+        let mut data = self.data.lock().await;
+        let sealed = self.seal_credentials(&credentials);
+        data.credentials.insert(key.to_owned(), sealed);
+        self.save(&data).await
+    }
+
+    async fn get_mfa_device(&self, key: &str) -> anyhow::Result<Option<MfaDevice>> {
+        Ok(self.data.lock().await.mfa_devices.get(key).cloned())
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> anyhow::Result<[u8; 32]> {
+    let mut key = [0; 32];
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|err| anyhow::format_err!("{}", err))?;
+    Ok(key)
+}
+
+fn seal(key: &[u8; 32], plaintext: &[u8]) -> SealedBlob {
+    let cipher = XSalsa20Poly1305::new(key.into());
+    let nonce = XSalsa20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .expect("secretbox encryption does not fail");
+    SealedBlob {
+        nonce: nonce.to_vec(),
+        ciphertext,
+    }
+}
+
+fn open(key: &[u8; 32], blob: &SealedBlob) -> anyhow::Result<Vec<u8>> {
+    let cipher = XSalsa20Poly1305::new(key.into());
+    let nonce = Nonce::from_slice(&blob.nonce);
+    cipher
+        .decrypt(nonce, blob.ciphertext.as_ref())
+        .map_err(|_| anyhow::format_err!("failed to decrypt (wrong passphrase?)"))
+}
@@ -0,0 +1,54 @@
+use super::{Credentials, MfaDevice, StorageBackend};
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+const SERVICE: &str = "aws-credentials-helper";
+
+/// Secrets never touch disk: each value is sealed in the OS keyring
+/// (Keychain / Secret Service / Credential Manager) under its own entry.
+pub(crate) struct Keyring;
+
+impl Keyring {
+    pub(crate) fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl StorageBackend for Keyring {
+    async fn get_credentials(&self, key: &str) -> anyhow::Result<Option<Credentials>> {
+        let username = format!("credentials:{}", key);
+        tokio::task::spawn_blocking(move || get(&username))
+            .await
+            .unwrap()
+    }
+
+    async fn put_credentials(&self, key: &str, credentials: Credentials) -> anyhow::Result<()> {
+        let username = format!("credentials:{}", key);
+        tokio::task::spawn_blocking(move || put(&username, &credentials))
+            .await
+            .unwrap()
+    }
+
+    async fn get_mfa_device(&self, key: &str) -> anyhow::Result<Option<MfaDevice>> {
+        let username = format!("mfa-device:{}", key);
+        tokio::task::spawn_blocking(move || get(&username))
+            .await
+            .unwrap()
+    }
+}
+
+fn get<T: DeserializeOwned>(username: &str) -> anyhow::Result<Option<T>> {
+    let entry = keyring::Entry::new(SERVICE, username)?;
+    match entry.get_password() {
+        Ok(password) => Ok(Some(serde_json::from_str(&password)?)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn put<T: Serialize>(username: &str, value: &T) -> anyhow::Result<()> {
+    let entry = keyring::Entry::new(SERVICE, username)?;
+    Ok(entry.set_password(&serde_json::to_string(value)?)?)
+}
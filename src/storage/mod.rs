@@ -0,0 +1,182 @@
+mod json_file;
+mod json_file_encrypted;
+mod keyring;
+
+use async_trait::async_trait;
+use chrono::offset::Utc;
+use chrono::{DateTime, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub(crate) use json_file::JsonFile;
+pub(crate) use json_file_encrypted::EncryptedJsonFile;
+pub(crate) use keyring::Keyring;
+
+/// A pluggable place to keep cached IAM credentials and MFA device config.
+///
+/// Implementations are free to store the whole map (the JSON file) or a
+/// single secret per key (an OS keyring), so access is modelled per-key
+/// rather than as a bulk load/save.
+#[async_trait]
+pub(crate) trait StorageBackend: Send + Sync {
+    async fn get_credentials(&self, key: &str) -> anyhow::Result<Option<Credentials>>;
+    async fn put_credentials(&self, key: &str, credentials: Credentials) -> anyhow::Result<()>;
+    async fn get_mfa_device(&self, key: &str) -> anyhow::Result<Option<MfaDevice>>;
+}
+
+#[async_trait]
+impl StorageBackend for Box<dyn StorageBackend> {
+    async fn get_credentials(&self, key: &str) -> anyhow::Result<Option<Credentials>> {
+        (**self).get_credentials(key).await
+    }
+
+    async fn put_credentials(&self, key: &str, credentials: Credentials) -> anyhow::Result<()> {
+        (**self).put_credentials(key, credentials).await
+    }
+
+    async fn get_mfa_device(&self, key: &str) -> anyhow::Result<Option<MfaDevice>> {
+        (**self).get_mfa_device(key).await
+    }
+}
+
+pub(crate) struct Storage<B> {
+    backend: B,
+}
+
+impl<B: StorageBackend> Storage<B> {
+    pub(crate) fn new(backend: B) -> Self {
+        Self { backend }
+    }
+
+    pub(crate) async fn get_credentials(&self, key: &str) -> anyhow::Result<Option<Credentials>> {
+        self.backend.get_credentials(key).await
+    }
+
+    pub(crate) async fn put_credentials(
+        &self,
+        key: &str,
+        credentials: Credentials,
+    ) -> anyhow::Result<()> {
+        self.backend.put_credentials(key, credentials).await
+    }
+
+    pub(crate) async fn get_mfa_device(&self, key: &str) -> anyhow::Result<Option<MfaDevice>> {
+        self.backend.get_mfa_device(key).await
+    }
+}
+
+impl Storage<Box<dyn StorageBackend>> {
+    /// Open the backend configured via `AWS_CREDENTIALS_HELPER_BACKEND`
+    /// (`json-file`, the default; `json-file-encrypted`; or `keyring`).
+    pub(crate) async fn open() -> anyhow::Result<Self> {
+        let backend: Box<dyn StorageBackend> = match std::env::var("AWS_CREDENTIALS_HELPER_BACKEND")
+        {
+            Ok(backend) if backend == "json-file" => Box::new(JsonFile::load().await?),
+            Ok(backend) if backend == "json-file-encrypted" => {
+                Box::new(EncryptedJsonFile::load().await?)
+            }
+            Ok(backend) if backend == "keyring" => Box::new(Keyring::new()),
+            Ok(backend) => anyhow::bail!("unknown storage backend {}", backend),
+            Err(std::env::VarError::NotPresent) => Box::new(JsonFile::load().await?),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(Self::new(backend))
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct Credentials {
+    pub(crate) access_key_id: String,
+    pub(crate) secret_access_key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) session_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) expiration: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) created_at: Option<DateTime<Utc>>,
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("access_key_id", &self.access_key_id)
+            .field("secret_access_key", &"***")
+            .field("session_token", &self.session_token.as_ref().map(|_| "***"))
+            .field("expiration", &self.expiration)
+            .field("created_at", &self.created_at)
+            .finish()
+    }
+}
+
+impl TryFrom<&aws_sdk_iam::model::AccessKey> for Credentials {
+    type Error = anyhow::Error;
+    fn try_from(value: &aws_sdk_iam::model::AccessKey) -> Result<Self, Self::Error> {
+        Ok(Self {
+            access_key_id: value
+                .access_key_id()
+                .map(str::to_owned)
+                .ok_or_else(|| anyhow::format_err!("missing access_key_id"))?,
+            secret_access_key: value
+                .secret_access_key()
+                .map(str::to_owned)
+                .ok_or_else(|| anyhow::format_err!("missing secret_access_key"))?,
+            session_token: None,
+            expiration: None,
+            created_at: value.create_date().map(|create_date| {
+                DateTime::<Utc>::from_utc(
+                    NaiveDateTime::from_timestamp(create_date.secs(), create_date.subsec_nanos()),
+                    Utc,
+                )
+            }),
+        })
+    }
+}
+
+impl TryFrom<&aws_sdk_sts::model::Credentials> for Credentials {
+    type Error = anyhow::Error;
+    fn try_from(value: &aws_sdk_sts::model::Credentials) -> Result<Self, Self::Error> {
+        Ok(Self {
+            access_key_id: value
+                .access_key_id()
+                .map(str::to_owned)
+                .ok_or_else(|| anyhow::format_err!("missing access_key_id"))?,
+            secret_access_key: value
+                .secret_access_key()
+                .map(str::to_owned)
+                .ok_or_else(|| anyhow::format_err!("missing secret_access_key"))?,
+            session_token: value.session_token().map(str::to_owned),
+            expiration: value.expiration().map(|expiration| {
+                DateTime::<Utc>::from_utc(
+                    NaiveDateTime::from_timestamp(expiration.secs(), expiration.subsec_nanos()),
+                    Utc,
+                )
+            }),
+            created_at: None,
+        })
+    }
+}
+
+impl From<Credentials> for aws_types::Credentials {
+    fn from(value: Credentials) -> Self {
+        Self::new(
+            value.access_key_id,
+            value.secret_access_key,
+            value.session_token,
+            value.expiration.map(Into::into),
+            env!("CARGO_PKG_NAME"),
+        )
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum MfaDevice {
+    Ykoath(Ykoath),
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct Ykoath {
+    pub(crate) name: String,
+}